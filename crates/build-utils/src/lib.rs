@@ -1,15 +1,302 @@
 use {
     cc::Build,
+    kconfparse::{ConfigEvaluator, ConfigValue, Tristate, TopLevel},
+    serde::Deserialize,
     std::{
-        collections::HashSet,
-        env::{join_paths, split_paths, var_os},
+        collections::{HashMap, HashSet},
+        env::{join_paths, split_paths, var_os, JoinPathsError},
         ffi::OsString,
-        fs::{create_dir, File},
+        fmt,
+        fs::{create_dir, read_to_string, File},
         io::Write,
-        path::PathBuf,
+        path::{Path, PathBuf},
     },
 };
 
+#[derive(Debug)]
+pub enum ModBuildError {
+    MissingEnvVar {
+        var: String,
+    },
+    InvalidUtf8 {
+        var: String,
+    },
+    UnknownMcu {
+        target: String,
+    },
+    MissingMcu,
+    JoinPaths {
+        source: JoinPathsError,
+    },
+    ManifestIo {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    ManifestParse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    FeatureCondition {
+        source: FeatureExprError,
+    },
+}
+
+impl fmt::Display for ModBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingEnvVar {
+                var,
+            } => write!(f, "environment variable {var} not set"),
+            Self::InvalidUtf8 {
+                var,
+            } => write!(f, "environment variable {var} is not valid UTF-8"),
+            Self::UnknownMcu {
+                target,
+            } => write!(f, "unable to determine MCU from target triple: {target}"),
+            Self::MissingMcu => write!(f, "unable to determine MCU: --cfg mcu=<mcu> not passed to rustc and no target triple specified"),
+            Self::JoinPaths {
+                source,
+            } => write!(f, "one or more include paths is not valid for a PATH-style environment variable: {source}"),
+            Self::ManifestIo {
+                path,
+                source,
+            } => write!(f, "failed to read {}: {source}", path.display()),
+            Self::ManifestParse {
+                path,
+                source,
+            } => write!(f, "failed to parse {}: {source}", path.display()),
+            Self::FeatureCondition {
+                source,
+            } => write!(f, "{source}"),
+        }
+    }
+}
+
+impl std::error::Error for ModBuildError {}
+
+pub trait ResultExt<T> {
+    fn or_warn(self) -> Result<T, ModBuildError>;
+}
+
+impl<T> ResultExt<T> for Result<T, ModBuildError> {
+    fn or_warn(self) -> Result<T, ModBuildError> {
+        if let Err(err) = &self {
+            println!("cargo:warning={err}");
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeatureExpr {
+    Feature(String),
+    Not(Box<FeatureExpr>),
+    And(Box<FeatureExpr>, Box<FeatureExpr>),
+    Or(Box<FeatureExpr>, Box<FeatureExpr>),
+}
+
+#[derive(Debug)]
+pub struct FeatureExprError {
+    condition: String,
+    token: String,
+}
+
+impl fmt::Display for FeatureExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid feature condition {:?}: unexpected token {:?}", self.condition, self.token)
+    }
+}
+
+impl std::error::Error for FeatureExprError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FeatureToken<'a> {
+    Ident(&'a str),
+    Comma,
+    Pipe,
+    Bang,
+    LParen,
+    RParen,
+}
+
+impl FeatureToken<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Ident(name) => name,
+            Self::Comma => ",",
+            Self::Pipe => "|",
+            Self::Bang => "!",
+            Self::LParen => "(",
+            Self::RParen => ")",
+        }
+    }
+}
+
+fn tokenize_feature_condition(condition: &str) -> Vec<FeatureToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = condition.char_indices().peekable();
+
+    while let Some(&(start, ch)) = chars.peek() {
+        match ch {
+            ',' => {
+                tokens.push(FeatureToken::Comma);
+                chars.next();
+            }
+            '|' => {
+                tokens.push(FeatureToken::Pipe);
+                chars.next();
+            }
+            '!' => {
+                tokens.push(FeatureToken::Bang);
+                chars.next();
+            }
+            '(' => {
+                tokens.push(FeatureToken::LParen);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(FeatureToken::RParen);
+                chars.next();
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut end = start;
+                while let Some(&(idx, c)) = chars.peek() {
+                    if matches!(c, ',' | '|' | '!' | '(' | ')') || c.is_whitespace() {
+                        break;
+                    }
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(FeatureToken::Ident(&condition[start..end]));
+            }
+        }
+    }
+
+    tokens
+}
+
+struct FeatureExprParser<'a> {
+    condition: &'a str,
+    tokens: Vec<FeatureToken<'a>>,
+    pos: usize,
+}
+
+impl<'a> FeatureExprParser<'a> {
+    fn peek(&self) -> Option<FeatureToken<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<FeatureToken<'a>> {
+        let token = self.peek();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error_at(&self, token: Option<FeatureToken<'a>>) -> FeatureExprError {
+        FeatureExprError {
+            condition: self.condition.to_string(),
+            token: token.map(|t| t.as_str().to_string()).unwrap_or_else(|| "<end of input>".to_string()),
+        }
+    }
+
+    // and_expr := or_expr ("," or_expr)*
+    fn parse_and(&mut self) -> Result<FeatureExpr, FeatureExprError> {
+        let mut expr = self.parse_or()?;
+        while self.peek() == Some(FeatureToken::Comma) {
+            self.bump();
+            let rhs = self.parse_or()?;
+            expr = FeatureExpr::And(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    // or_expr := not_expr ("|" not_expr)*
+    fn parse_or(&mut self) -> Result<FeatureExpr, FeatureExprError> {
+        let mut expr = self.parse_not()?;
+        while self.peek() == Some(FeatureToken::Pipe) {
+            self.bump();
+            let rhs = self.parse_not()?;
+            expr = FeatureExpr::Or(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    // not_expr := "!" not_expr | atom
+    fn parse_not(&mut self) -> Result<FeatureExpr, FeatureExprError> {
+        if self.peek() == Some(FeatureToken::Bang) {
+            self.bump();
+            return Ok(FeatureExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    // atom := "(" and_expr ")" | ident
+    fn parse_atom(&mut self) -> Result<FeatureExpr, FeatureExprError> {
+        match self.bump() {
+            Some(FeatureToken::Ident(name)) => Ok(FeatureExpr::Feature(name.to_string())),
+            Some(FeatureToken::LParen) => {
+                let expr = self.parse_and()?;
+                match self.bump() {
+                    Some(FeatureToken::RParen) => Ok(expr),
+                    other => Err(self.error_at(other)),
+                }
+            }
+            other => Err(self.error_at(other)),
+        }
+    }
+}
+
+impl FeatureExpr {
+    pub fn parse(condition: &str) -> Result<Self, FeatureExprError> {
+        let mut parser = FeatureExprParser {
+            condition,
+            tokens: tokenize_feature_condition(condition),
+            pos: 0,
+        };
+
+        let expr = parser.parse_and()?;
+        if parser.pos != parser.tokens.len() {
+            let token = parser.peek();
+            return Err(parser.error_at(token));
+        }
+
+        Ok(expr)
+    }
+
+    pub fn eval(&self, features: &HashSet<String>) -> bool {
+        match self {
+            Self::Feature(name) => features.contains(name),
+            Self::Not(inner) => !inner.eval(features),
+            Self::And(lhs, rhs) => lhs.eval(features) && rhs.eval(features),
+            Self::Or(lhs, rhs) => lhs.eval(features) || rhs.eval(features),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ComponentManifest {
+    base_dir: String,
+    #[serde(default)]
+    sources: Vec<String>,
+    #[serde(default)]
+    include_dirs: Vec<String>,
+    #[serde(default)]
+    exported_include_dirs: Vec<String>,
+    #[serde(default)]
+    feature_sources: Vec<FeatureSources>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeatureSources {
+    condition: String,
+    files: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct ModBuild {
     pub build: Build,
@@ -66,33 +353,71 @@ impl ModBuild {
     }
 
     pub fn mcu() -> String {
+        Self::try_mcu().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_mcu() -> Result<String, ModBuildError> {
         if let Some(mcu) = var_os("CARGO_CFG_MCU") {
-            return mcu.into_string().expect("MCU name is not valid UTF-8");
-        }
-
-        if let Some(target) = var_os("TARGET") {
-            let target = target.into_string().expect("TARGET name is not valid UTF-8");
-            if target.starts_with("xtensa-esp32-") {
-                return "esp32".to_string();
-            } else if target.starts_with("xtensa-esp32s2-") {
-                return "esp32s2".to_string();
-            } else if target.starts_with("xtensa-esp32s3-") {
-                return "esp32s3".to_string();
-            } else if target.starts_with("riscv32imc-") {
-                return "esp32c3".to_string();
-            }
+            return mcu
+                .into_string()
+                .map_err(|_| ModBuildError::InvalidUtf8 {
+                    var: "CARGO_CFG_MCU".to_string(),
+                })
+                .or_warn();
+        }
+
+        let Some(target) = var_os("TARGET") else {
+            return Err(ModBuildError::MissingMcu).or_warn();
+        };
+
+        let target = target
+            .into_string()
+            .map_err(|_| ModBuildError::InvalidUtf8 {
+                var: "TARGET".to_string(),
+            })
+            .or_warn()?;
 
-            panic!("Unable to determine MCU from target triple: {target}");
+        if target.starts_with("xtensa-esp32-") {
+            return Ok("esp32".to_string());
+        } else if target.starts_with("xtensa-esp32s2-") {
+            return Ok("esp32s2".to_string());
+        } else if target.starts_with("xtensa-esp32s3-") {
+            return Ok("esp32s3".to_string());
+        } else if target.starts_with("riscv32imc-") {
+            return Ok("esp32c3".to_string());
         }
 
-        panic!("Unable to determine MCU: --cfg mcu=<mcu> not passed to rustc and no target triple specified");
+        Err(ModBuildError::UnknownMcu {
+            target,
+        })
+        .or_warn()
     }
 
-    pub fn generate_sdkconfig(&mut self) -> PathBuf {
+    pub fn generate_sdkconfig(&mut self, blocks: &[TopLevel<'_>], values: HashMap<String, String>) -> PathBuf {
         let sdkconfig_filename = Self::generated_include_dir().join("sdkconfig.h");
         let mut sdkconfig_file = File::create(&sdkconfig_filename).unwrap();
 
         writeln!(sdkconfig_file, "#pragma once").unwrap();
+
+        let mut evaluator = ConfigEvaluator::new(blocks, values);
+        let mut names: Vec<&str> = evaluator.symbols().collect();
+        names.sort_unstable();
+
+        for name in names {
+            if !evaluator.is_visible(name) {
+                continue;
+            }
+
+            let value = evaluator.resolve(name);
+            if let Some(define) = format_define(name, &value) {
+                writeln!(sdkconfig_file, "{define}").unwrap();
+            }
+
+            if value.is_truthy() {
+                println!("cargo:rustc-cfg=config_{}", name.to_lowercase());
+            }
+        }
+
         sdkconfig_file.flush().unwrap();
         drop(sdkconfig_file);
 
@@ -100,61 +425,147 @@ impl ModBuild {
     }
 
     pub fn include_dirs_to_path(&self) -> String {
-        let path_var = join_paths(&self.exported_include_dirs).expect("One or more paths is not valid for a PATH-style environment variable");
-        path_var.into_string().expect("The resulting PATH-style environment variable is not valid UTF-8")
+        self.try_include_dirs_to_path().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_include_dirs_to_path(&self) -> Result<String, ModBuildError> {
+        let path_var = join_paths(&self.exported_include_dirs)
+            .map_err(|source| ModBuildError::JoinPaths {
+                source,
+            })
+            .or_warn()?;
+        path_var
+            .into_string()
+            .map_err(|_| ModBuildError::InvalidUtf8 {
+                var: "include dirs".to_string(),
+            })
+            .or_warn()
     }
 
     pub fn add_library_include(&mut self, lib_name: impl AsRef<str>) {
+        self.try_add_library_include(lib_name).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_add_library_include(&mut self, lib_name: impl AsRef<str>) -> Result<(), ModBuildError> {
         let lib_name = lib_name.as_ref().to_uppercase().replace('-', "_");
         let env_var = format!("DEP_{lib_name}_INCLUDE");
-        let Some(includes) = var_os(&env_var) else {
-            panic!("Environment variable {env_var} not set");
-        };
+        let includes = var_os(&env_var)
+            .ok_or(ModBuildError::MissingEnvVar {
+                var: env_var,
+            })
+            .or_warn()?;
 
         for path_el in split_paths(&includes) {
             self.build.include(path_el);
         }
+
+        Ok(())
     }
 
     pub fn add_component_source_files(&mut self, base_dir: impl AsRef<str>, component_src_files: &[&str]) {
-        let component_base_dir = base_dir.as_ref().replace("${mcu}", &Self::mcu());
+        self.try_add_component_source_files(base_dir, component_src_files).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_add_component_source_files(&mut self, base_dir: impl AsRef<str>, component_src_files: &[&str]) -> Result<(), ModBuildError> {
+        let mcu = Self::try_mcu()?;
+        let component_base_dir = base_dir.as_ref().replace("${mcu}", &mcu);
         let dir = Self::manifest_dir().join(&component_base_dir);
         for file in component_src_files {
-            let file = file.replace("${mcu}", &Self::mcu());
+            let file = file.replace("${mcu}", &mcu);
             self.build.file(dir.join(&file));
             println!("cargo:rerun-if-changed={component_base_dir}/{file}");
         }
+        Ok(())
     }
 
     pub fn add_feature_component_source_files(&mut self, base_dir: impl AsRef<str>, sources: &[(&str, &[&str])]) {
+        self.try_add_feature_component_source_files(base_dir, sources).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_add_feature_component_source_files(&mut self, base_dir: impl AsRef<str>, sources: &[(&str, &[&str])]) -> Result<(), ModBuildError> {
         let base_dir = base_dir.as_ref();
 
-        'feature_loop:
         for (feature_condition, files) in sources.iter() {
-            for feature in feature_condition.split(',') {
-                if let Some(feature) = feature.strip_prefix('!') {
-                    if self.features.contains(feature) {
-                        continue 'feature_loop;
-                    }
-                } else if !self.features.contains(feature) {
-                    continue 'feature_loop;
-                }
+            let expr = FeatureExpr::parse(feature_condition)
+                .map_err(|source| ModBuildError::FeatureCondition {
+                    source,
+                })
+                .or_warn()?;
+
+            if expr.eval(&self.features) {
+                self.try_add_component_source_files(base_dir, files)?;
             }
-            
-            // All feature tests passed.
-            self.add_component_source_files(base_dir, files)
         }
+
+        Ok(())
     }
 
     pub fn add_component_include_dirs(&mut self, base_dir: impl AsRef<str>, component_include_dirs: &[&str]) {
-        let base_dir = base_dir.as_ref().replace("${mcu}", &Self::mcu());
+        self.try_add_component_include_dirs(base_dir, component_include_dirs).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_add_component_include_dirs(&mut self, base_dir: impl AsRef<str>, component_include_dirs: &[&str]) -> Result<(), ModBuildError> {
+        let mcu = Self::try_mcu()?;
+        let base_dir = base_dir.as_ref().replace("${mcu}", &mcu);
         let dir = Self::manifest_dir().join(&base_dir);
         for include_dir in component_include_dirs {
-            let include_dir = include_dir.replace("${mcu}", &Self::mcu());
+            let include_dir = include_dir.replace("${mcu}", &mcu);
             self.build.include(dir.join(&include_dir));
             println!("cargo:rerun-if-changed={base_dir}/{include_dir}");
             self.exported_include_dirs.push(OsString::from(dir.join(include_dir)));
         }
+        Ok(())
+    }
+
+    pub fn apply_manifest(&mut self, path: impl AsRef<Path>) {
+        self.try_apply_manifest(path).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_apply_manifest(&mut self, path: impl AsRef<Path>) -> Result<(), ModBuildError> {
+        let path = path.as_ref();
+
+        let contents = read_to_string(path)
+            .map_err(|source| ModBuildError::ManifestIo {
+                path: path.to_path_buf(),
+                source,
+            })
+            .or_warn()?;
+        let manifest: ComponentManifest = toml::from_str(&contents)
+            .map_err(|source| ModBuildError::ManifestParse {
+                path: path.to_path_buf(),
+                source,
+            })
+            .or_warn()?;
+
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        if !manifest.sources.is_empty() {
+            let sources: Vec<&str> = manifest.sources.iter().map(String::as_str).collect();
+            self.try_add_component_source_files(&manifest.base_dir, &sources)?;
+        }
+
+        for feature_sources in &manifest.feature_sources {
+            let files: Vec<&str> = feature_sources.files.iter().map(String::as_str).collect();
+            self.try_add_feature_component_source_files(&manifest.base_dir, &[(feature_sources.condition.as_str(), files.as_slice())])?;
+        }
+
+        if !manifest.include_dirs.is_empty() {
+            let mcu = Self::try_mcu()?;
+            let base_dir = manifest.base_dir.replace("${mcu}", &mcu);
+            let dir = Self::manifest_dir().join(&base_dir);
+            for include_dir in &manifest.include_dirs {
+                let include_dir = include_dir.replace("${mcu}", &mcu);
+                self.build.include(dir.join(&include_dir));
+                println!("cargo:rerun-if-changed={base_dir}/{include_dir}");
+            }
+        }
+
+        if !manifest.exported_include_dirs.is_empty() {
+            let exported: Vec<&str> = manifest.exported_include_dirs.iter().map(String::as_str).collect();
+            self.try_add_component_include_dirs(&manifest.base_dir, &exported)?;
+        }
+
+        Ok(())
     }
 
     pub fn compile_library(&mut self, library_name: impl AsRef<str>) {
@@ -163,4 +574,128 @@ impl ModBuild {
         println!("cargo:rustc-link-lib=static={}", library_name);
         println!("cargo:INCLUDE={}", self.include_dirs_to_path());
     }
+}
+
+fn format_define(name: &str, value: &ConfigValue) -> Option<String> {
+    match value {
+        ConfigValue::Bool(false) => None,
+        ConfigValue::Bool(true) => Some(format!("#define CONFIG_{name} 1")),
+        ConfigValue::Tristate(Tristate::No) => None,
+        ConfigValue::Tristate(Tristate::Yes) => Some(format!("#define CONFIG_{name} 1")),
+        ConfigValue::Tristate(Tristate::Module) => Some(format!("#define CONFIG_{name}_MODULE 1")),
+        ConfigValue::Number(text) => Some(format!("#define CONFIG_{name} {text}")),
+        ConfigValue::Str(text) => Some(format!("#define CONFIG_{name} \"{text}\"")),
+    }
+}
+
+#[cfg(test)]
+mod feature_expr {
+    use super::*;
+
+    fn features(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_or_and_group() {
+        let expr = FeatureExpr::parse("wifi,(bt|ble),!coex_disabled").unwrap();
+
+        assert!(expr.eval(&features(&["wifi", "ble"])));
+        assert!(expr.eval(&features(&["wifi", "bt"])));
+        assert!(!expr.eval(&features(&["wifi", "ble", "coex_disabled"])));
+        assert!(!expr.eval(&features(&["wifi"])));
+        assert!(!expr.eval(&features(&["bt"])));
+    }
+
+    #[test]
+    fn test_nested_grouping() {
+        let expr = FeatureExpr::parse("(wifi|bt),!(legacy|deprecated)").unwrap();
+
+        assert!(expr.eval(&features(&["wifi"])));
+        assert!(!expr.eval(&features(&["wifi", "legacy"])));
+        assert!(!expr.eval(&features(&[])));
+    }
+
+    #[test]
+    fn test_malformed_reports_offending_token() {
+        let err = FeatureExpr::parse("wifi,,bt").unwrap_err();
+        assert_eq!(err.token, ",");
+
+        let err = FeatureExpr::parse("wifi|(bt").unwrap_err();
+        assert_eq!(err.token, "<end of input>");
+
+        let err = FeatureExpr::parse("(wifi").unwrap_err();
+        assert_eq!(err.token, "<end of input>");
+    }
+}
+
+#[cfg(test)]
+mod build {
+    use super::*;
+
+    fn mod_build() -> ModBuild {
+        ModBuild {
+            build: Build::new(),
+            exported_include_dirs: Vec::new(),
+            features: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_try_add_library_include_missing_env_returns_err() {
+        let mut build = mod_build();
+        let err = build.try_add_library_include("definitely-not-a-linked-component").unwrap_err();
+        assert!(matches!(err, ModBuildError::MissingEnvVar { var } if var == "DEP_DEFINITELY_NOT_A_LINKED_COMPONENT_INCLUDE"));
+    }
+
+    #[test]
+    fn test_apply_manifest_missing_file_returns_manifest_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut build = mod_build();
+        let err = build.try_apply_manifest(dir.path().join("missing.toml")).unwrap_err();
+        assert!(matches!(err, ModBuildError::ManifestIo { .. }));
+    }
+
+    #[test]
+    fn test_apply_manifest_malformed_toml_returns_manifest_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("component.toml");
+        std::fs::write(&manifest_path, "base_dir = [not valid toml").unwrap();
+        let mut build = mod_build();
+        let err = build.try_apply_manifest(&manifest_path).unwrap_err();
+        assert!(matches!(err, ModBuildError::ManifestParse { .. }));
+    }
+
+    #[test]
+    fn test_apply_manifest_registers_sources_and_include_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("component.toml");
+        std::fs::write(
+            &manifest_path,
+            "\
+base_dir = \"component\"
+sources = [\"main.c\"]
+include_dirs = [\"include\"]
+exported_include_dirs = [\"include\"]
+",
+        )
+        .unwrap();
+
+        // `try_add_component_source_files`/`try_add_component_include_dirs` resolve
+        // `${mcu}` via `try_mcu`, which otherwise requires a real `TARGET`.
+        unsafe {
+            std::env::set_var("CARGO_CFG_MCU", "esp32");
+        }
+        let mut build = mod_build();
+        let result = build.try_apply_manifest(&manifest_path);
+        unsafe {
+            std::env::remove_var("CARGO_CFG_MCU");
+        }
+        result.unwrap();
+
+        let files: Vec<&Path> = build.build.get_files().collect();
+        assert!(files.iter().any(|f| f.ends_with("component/main.c")));
+        assert_eq!(build.exported_include_dirs.len(), 1);
+        assert!(build.exported_include_dirs[0].to_str().unwrap().ends_with("component/include"));
+    }
 }
\ No newline at end of file
@@ -1,12 +1,20 @@
 #![allow(clippy::result_large_err)]
 
 use {
+    glob::glob,
     pest::{
         error::{Error, ErrorVariant},
         iterators::Pair,
+        Parser as _, Span,
     },
     pest_derive::Parser,
-    std::borrow::Cow,
+    std::{
+        borrow::Cow,
+        collections::{HashMap, HashSet},
+        fmt,
+        fs::read_to_string,
+        path::{Path, PathBuf},
+    },
 };
 
 macro_rules! try_from_pairs {
@@ -53,6 +61,11 @@ impl<'a> TryFrom<Pair<'a, Rule>> for KConfigFile<'a> {
 
         let mut blocks = Vec::new();
         for pair in pair.into_inner() {
+            // `file`'s trailing EOI token surfaces as a pair of its own; it carries
+            // no content and isn't a top-level block.
+            if pair.as_rule() == Rule::EOI {
+                continue;
+            }
             blocks.push(TopLevel::try_from(pair)?);
         }
 
@@ -67,6 +80,12 @@ try_from_pairs!(KConfigFile, file);
 #[derive(Debug, Eq, PartialEq)]
 pub enum TopLevel<'a> {
     SourceDirective(SourceDirective<'a>),
+    Config(ConfigEntry<'a>),
+    Menu(MenuEntry<'a>),
+    Choice(ChoiceEntry<'a>),
+    If(IfEntry<'a>),
+    Comment(CommentEntry<'a>),
+    MainMenu(MainMenuEntry<'a>),
 }
 
 impl<'a> TryFrom<Pair<'a, Rule>> for TopLevel<'a> {
@@ -80,7 +99,13 @@ impl<'a> TryFrom<Pair<'a, Rule>> for TopLevel<'a> {
         assert!(pairs.next().is_none());
 
         match pair.as_rule() {
-            Rule::source_directive => Ok(Self::SourceDirective(SourceDirective::try_from(pair).unwrap())),
+            Rule::source_directive => Ok(Self::SourceDirective(SourceDirective::try_from(pair)?)),
+            Rule::config_entry => Ok(Self::Config(ConfigEntry::try_from(pair)?)),
+            Rule::menu_entry => Ok(Self::Menu(MenuEntry::try_from(pair)?)),
+            Rule::choice_entry => Ok(Self::Choice(ChoiceEntry::try_from(pair)?)),
+            Rule::if_entry => Ok(Self::If(IfEntry::try_from(pair)?)),
+            Rule::comment_entry => Ok(Self::Comment(CommentEntry::try_from(pair)?)),
+            Rule::mainmenu_entry => Ok(Self::MainMenu(MainMenuEntry::try_from(pair)?)),
             _ => unreachable!("not a top-level: {pair:?}"),
         }
     }
@@ -162,6 +187,571 @@ impl<'a> TryFrom<Pair<'a, Rule>> for SourceDirective<'a> {
     }
 }
 
+/// The type of a `config`/`menuconfig` symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SymbolType {
+    Bool,
+    Tristate,
+    Int,
+    Hex,
+    String,
+}
+
+impl TryFrom<Pair<'_, Rule>> for SymbolType {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Error<Rule>> {
+        match pair.as_rule() {
+            Rule::K_BOOL => Ok(Self::Bool),
+            Rule::K_TRISTATE => Ok(Self::Tristate),
+            Rule::K_INT => Ok(Self::Int),
+            Rule::K_HEX => Ok(Self::Hex),
+            Rule::K_STRING => Ok(Self::String),
+            Rule::symbol_type => Self::try_from(pair.into_inner().next().unwrap()),
+            _ => Err(Error::new_from_span(
+                ErrorVariant::CustomError {
+                    message: format!("not a symbol type: {pair:?}"),
+                },
+                pair.as_span(),
+            )),
+        }
+    }
+}
+
+/// A comparison operator in a Kconfig expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl TryFrom<Pair<'_, Rule>> for CompareOp {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, compare_op);
+
+        match pair.as_str() {
+            "=" => Ok(Self::Eq),
+            "!=" => Ok(Self::Ne),
+            "<" => Ok(Self::Lt),
+            "<=" => Ok(Self::Le),
+            ">" => Ok(Self::Gt),
+            ">=" => Ok(Self::Ge),
+            other => Err(Error::new_from_span(
+                ErrorVariant::CustomError {
+                    message: format!("not a comparison operator: {other}"),
+                },
+                pair.as_span(),
+            )),
+        }
+    }
+}
+
+/// A Kconfig boolean/tristate expression, as used in `depends on`, `default`,
+/// `if`, `select`/`imply` conditions, and `range` bounds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expr<'a> {
+    Symbol(Cow<'a, str>),
+    Literal(Cow<'a, str>),
+    Number(Cow<'a, str>),
+    Not(Box<Expr<'a>>),
+    And(Box<Expr<'a>>, Box<Expr<'a>>),
+    Or(Box<Expr<'a>>, Box<Expr<'a>>),
+    Compare(Box<Expr<'a>>, CompareOp, Box<Expr<'a>>),
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for Expr<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        match pair.as_rule() {
+            Rule::expr => Self::try_from(pair.into_inner().next().unwrap()),
+            Rule::or_expr => Self::parse_or(pair),
+            Rule::and_expr => Self::parse_and(pair),
+            Rule::not_expr => Self::parse_not(pair),
+            Rule::compare_expr => Self::parse_compare(pair),
+            Rule::atom => Self::parse_atom(pair),
+            _ => Err(Error::new_from_span(
+                ErrorVariant::CustomError {
+                    message: format!("not an expression: {pair:?}"),
+                },
+                pair.as_span(),
+            )),
+        }
+    }
+}
+
+impl<'a> Expr<'a> {
+    fn parse_or(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, or_expr);
+
+        let mut pairs = pair.into_inner();
+        let mut expr = Self::parse_and(pairs.next().unwrap())?;
+        for pair in pairs {
+            expr = Self::Or(Box::new(expr), Box::new(Self::parse_and(pair)?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, and_expr);
+
+        let mut pairs = pair.into_inner();
+        let mut expr = Self::parse_not(pairs.next().unwrap())?;
+        for pair in pairs {
+            expr = Self::And(Box::new(expr), Box::new(Self::parse_not(pair)?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, not_expr);
+
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::not_expr => Ok(Self::Not(Box::new(Self::parse_not(inner)?))),
+            Rule::compare_expr => Self::parse_compare(inner),
+            other => unreachable!("unexpected token in not expression: {other:?}"),
+        }
+    }
+
+    fn parse_compare(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, compare_expr);
+
+        let mut pairs = pair.into_inner();
+        let lhs = Self::parse_atom(pairs.next().unwrap())?;
+
+        let Some(op_pair) = pairs.next() else {
+            return Ok(lhs);
+        };
+        let op = CompareOp::try_from(op_pair)?;
+        let rhs = Self::parse_atom(pairs.next().unwrap())?;
+        Ok(Self::Compare(Box::new(lhs), op, Box::new(rhs)))
+    }
+
+    fn parse_atom(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, atom);
+
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::expr => Self::try_from(inner),
+            Rule::symbol_name => Ok(Self::Symbol(Cow::Borrowed(inner.as_str()))),
+            Rule::string => Ok(Self::Literal(parse_string_literal(&inner)?)),
+            Rule::number => Ok(Self::Number(Cow::Borrowed(inner.as_str()))),
+            other => unreachable!("unexpected token in atom: {other:?}"),
+        }
+    }
+}
+
+fn parse_if_cond<'a>(pair: Pair<'a, Rule>) -> Result<Expr<'a>, Error<Rule>> {
+    check_rule!(pair, if_cond);
+
+    let mut pairs = pair.into_inner();
+    pairs.next().unwrap(); // K_IF
+    Expr::try_from(pairs.next().unwrap())
+}
+
+/// A single attribute line inside a `config`/`menuconfig`/`choice` body.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigAttr<'a> {
+    Type {
+        symbol_type: SymbolType,
+        prompt: Option<Cow<'a, str>>,
+        condition: Option<Expr<'a>>,
+    },
+    Prompt {
+        text: Cow<'a, str>,
+        condition: Option<Expr<'a>>,
+    },
+    Default {
+        value: Expr<'a>,
+        condition: Option<Expr<'a>>,
+    },
+    DependsOn {
+        condition: Expr<'a>,
+    },
+    Select {
+        symbol: Cow<'a, str>,
+        condition: Option<Expr<'a>>,
+    },
+    Imply {
+        symbol: Cow<'a, str>,
+        condition: Option<Expr<'a>>,
+    },
+    Range {
+        low: Expr<'a>,
+        high: Expr<'a>,
+        condition: Option<Expr<'a>>,
+    },
+    Help {
+        text: Cow<'a, str>,
+    },
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for ConfigAttr<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, config_attr);
+
+        let mut pairs = pair.into_inner();
+        let pair = pairs.next().unwrap();
+        assert!(pairs.next().is_none());
+
+        match pair.as_rule() {
+            Rule::type_line => Self::try_from_type_line(pair),
+            Rule::prompt_line => Self::try_from_prompt_line(pair),
+            Rule::default_line => Self::try_from_default_line(pair),
+            Rule::depends_line => Self::try_from_depends_line(pair),
+            Rule::select_line => Self::try_from_select_line(pair),
+            Rule::imply_line => Self::try_from_imply_line(pair),
+            Rule::range_line => Self::try_from_range_line(pair),
+            Rule::help_block => Self::try_from_help_block(pair),
+            _ => unreachable!("not a config attribute: {pair:?}"),
+        }
+    }
+}
+
+impl<'a> ConfigAttr<'a> {
+    fn try_from_type_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, type_line);
+
+        let mut pairs = pair.into_inner();
+        let symbol_type = SymbolType::try_from(pairs.next().unwrap())?;
+
+        let mut prompt = None;
+        let mut condition = None;
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::string => prompt = Some(parse_string_literal(&pair)?),
+                Rule::if_cond => condition = Some(parse_if_cond(pair)?),
+                other => unreachable!("unexpected token in type line: {other:?}"),
+            }
+        }
+
+        Ok(Self::Type {
+            symbol_type,
+            prompt,
+            condition,
+        })
+    }
+
+    fn try_from_prompt_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, prompt_line);
+
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_PROMPT
+        let text = parse_string_literal(&pairs.next().unwrap())?;
+        let condition = pairs.next().map(parse_if_cond).transpose()?;
+
+        Ok(Self::Prompt {
+            text,
+            condition,
+        })
+    }
+
+    fn try_from_default_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, default_line);
+
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_DEFAULT
+        let value = Expr::try_from(pairs.next().unwrap())?;
+        let condition = pairs.next().map(parse_if_cond).transpose()?;
+
+        Ok(Self::Default {
+            value,
+            condition,
+        })
+    }
+
+    fn try_from_depends_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, depends_line);
+
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_DEPENDS
+        pairs.next().unwrap(); // K_ON
+        let condition = Expr::try_from(pairs.next().unwrap())?;
+
+        Ok(Self::DependsOn {
+            condition,
+        })
+    }
+
+    fn try_from_select_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, select_line);
+
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_SELECT
+        let symbol = Cow::Borrowed(pairs.next().unwrap().as_str());
+        let condition = pairs.next().map(parse_if_cond).transpose()?;
+
+        Ok(Self::Select {
+            symbol,
+            condition,
+        })
+    }
+
+    fn try_from_imply_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, imply_line);
+
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_IMPLY
+        let symbol = Cow::Borrowed(pairs.next().unwrap().as_str());
+        let condition = pairs.next().map(parse_if_cond).transpose()?;
+
+        Ok(Self::Imply {
+            symbol,
+            condition,
+        })
+    }
+
+    fn try_from_range_line(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, range_line);
+
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_RANGE
+        let low = Expr::try_from(pairs.next().unwrap())?;
+        let high = Expr::try_from(pairs.next().unwrap())?;
+        let condition = pairs.next().map(parse_if_cond).transpose()?;
+
+        Ok(Self::Range {
+            low,
+            high,
+            condition,
+        })
+    }
+
+    fn try_from_help_block(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, help_block);
+
+        let text = pair
+            .into_inner()
+            .filter(|pair| pair.as_rule() == Rule::help_line)
+            .map(|pair| pair.as_str().trim_end_matches(['\n', '\r']))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(Self::Help {
+            text: Cow::Owned(text),
+        })
+    }
+}
+
+/// A `config` or `menuconfig` symbol definition.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ConfigEntry<'a> {
+    /// `true` for `menuconfig`, `false` for `config`.
+    pub is_menu: bool,
+    pub name: Cow<'a, str>,
+    pub attrs: Vec<ConfigAttr<'a>>,
+    pub span: Span<'a>,
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for ConfigEntry<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, config_entry);
+
+        let span = pair.as_span();
+        let mut pairs = pair.into_inner();
+
+        let keyword = pairs.next().unwrap();
+        let is_menu = matches!(keyword.as_rule(), Rule::K_MENUCONFIG);
+
+        let name_pair = pairs.next().unwrap();
+        check_rule!(name_pair, symbol_name);
+        let name = Cow::Borrowed(name_pair.as_str());
+
+        let mut attrs = Vec::new();
+        for pair in pairs {
+            attrs.push(ConfigAttr::try_from(pair)?);
+        }
+
+        Ok(Self {
+            is_menu,
+            name,
+            attrs,
+            span,
+        })
+    }
+}
+
+/// A `menu`/`endmenu` block.
+#[derive(Debug, Eq, PartialEq)]
+pub struct MenuEntry<'a> {
+    pub prompt: Cow<'a, str>,
+    pub items: Vec<TopLevel<'a>>,
+    pub span: Span<'a>,
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for MenuEntry<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, menu_entry);
+
+        let span = pair.as_span();
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_MENU
+        let prompt = parse_string_literal(&pairs.next().unwrap())?;
+
+        let mut items = Vec::new();
+        for pair in &mut pairs {
+            if pair.as_rule() == Rule::K_ENDMENU {
+                break;
+            }
+            items.push(TopLevel::try_from(pair)?);
+        }
+
+        Ok(Self {
+            prompt,
+            items,
+            span,
+        })
+    }
+}
+
+/// A `choice`/`endchoice` block.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ChoiceEntry<'a> {
+    pub name: Option<Cow<'a, str>>,
+    pub attrs: Vec<ConfigAttr<'a>>,
+    pub items: Vec<TopLevel<'a>>,
+    pub span: Span<'a>,
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for ChoiceEntry<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, choice_entry);
+
+        let span = pair.as_span();
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_CHOICE
+
+        let name = match pairs.peek() {
+            Some(pair) if pair.as_rule() == Rule::symbol_name => {
+                let pair = pairs.next().unwrap();
+                Some(Cow::Borrowed(pair.as_str()))
+            }
+            _ => None,
+        };
+
+        let mut attrs = Vec::new();
+        let mut items = Vec::new();
+        for pair in &mut pairs {
+            match pair.as_rule() {
+                Rule::config_attr => attrs.push(ConfigAttr::try_from(pair)?),
+                Rule::top_level => items.push(TopLevel::try_from(pair)?),
+                Rule::K_ENDCHOICE => break,
+                other => unreachable!("unexpected token in choice: {other:?}"),
+            }
+        }
+
+        Ok(Self {
+            name,
+            attrs,
+            items,
+            span,
+        })
+    }
+}
+
+/// An `if`/`endif` block.
+#[derive(Debug, Eq, PartialEq)]
+pub struct IfEntry<'a> {
+    pub condition: Expr<'a>,
+    pub items: Vec<TopLevel<'a>>,
+    pub span: Span<'a>,
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for IfEntry<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, if_entry);
+
+        let span = pair.as_span();
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_IF
+        let condition = Expr::try_from(pairs.next().unwrap())?;
+
+        let mut items = Vec::new();
+        for pair in &mut pairs {
+            if pair.as_rule() == Rule::K_ENDIF {
+                break;
+            }
+            items.push(TopLevel::try_from(pair)?);
+        }
+
+        Ok(Self {
+            condition,
+            items,
+            span,
+        })
+    }
+}
+
+/// A `comment` statement.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CommentEntry<'a> {
+    pub text: Cow<'a, str>,
+    pub condition: Option<Expr<'a>>,
+    pub span: Span<'a>,
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for CommentEntry<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, comment_entry);
+
+        let span = pair.as_span();
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_COMMENT
+        let text = parse_string_literal(&pairs.next().unwrap())?;
+        let condition = pairs.next().map(parse_if_cond).transpose()?;
+
+        Ok(Self {
+            text,
+            condition,
+            span,
+        })
+    }
+}
+
+/// A `mainmenu` statement.
+#[derive(Debug, Eq, PartialEq)]
+pub struct MainMenuEntry<'a> {
+    pub title: Cow<'a, str>,
+    pub span: Span<'a>,
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for MainMenuEntry<'a> {
+    type Error = Error<Rule>;
+
+    fn try_from(pair: Pair<'a, Rule>) -> Result<Self, Error<Rule>> {
+        check_rule!(pair, mainmenu_entry);
+
+        let span = pair.as_span();
+        let mut pairs = pair.into_inner();
+        pairs.next().unwrap(); // K_MAINMENU
+        let title = parse_string_literal(&pairs.next().unwrap())?;
+
+        Ok(Self {
+            title,
+            span,
+        })
+    }
+}
+
 fn parse_string_literal<'a>(pair: &Pair<'a, Rule>) -> Result<Cow<'a, str>, Error<Rule>> {
     assert_eq!(pair.as_rule(), Rule::string);
     let literal = pair.as_str();
@@ -246,6 +836,658 @@ fn parse_string_literal<'a>(pair: &Pair<'a, Rule>) -> Result<Cow<'a, str>, Error
     }
 }
 
+/// A parsed top-level Kconfig block together with the (resolved) path of the
+/// file it was parsed from.
+#[derive(Debug)]
+pub struct LoadedBlock<'a> {
+    pub path: PathBuf,
+    pub block: TopLevel<'a>,
+}
+
+/// Error produced while recursively resolving `source`-family directives.
+#[derive(Debug)]
+pub enum KConfigLoadError {
+    /// Reading a Kconfig file from disk failed.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// A file failed to parse as Kconfig.
+    Parse {
+        path: PathBuf,
+        source: Box<Error<Rule>>,
+    },
+
+    /// A `filename_glob` (after `$(VAR)` expansion) was not a valid glob pattern.
+    Pattern {
+        path: PathBuf,
+        glob: String,
+        source: glob::PatternError,
+    },
+
+    /// A non-optional `source`/`rsource` directive matched no files.
+    NoMatch {
+        path: PathBuf,
+        glob: String,
+    },
+
+    /// A file sources itself, directly or transitively.
+    Cycle {
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for KConfigLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io {
+                path,
+                source,
+            } => write!(f, "failed to read {}: {source}", path.display()),
+            Self::Parse {
+                path,
+                source,
+            } => write!(f, "failed to parse {}: {source}", path.display()),
+            Self::Pattern {
+                path,
+                glob,
+                source,
+            } => write!(f, "invalid glob {glob:?} sourced from {}: {source}", path.display()),
+            Self::NoMatch {
+                path,
+                glob,
+            } => write!(f, "source {glob:?} in {} matched no files", path.display()),
+            Self::Cycle {
+                path,
+            } => write!(f, "{} sources itself, directly or transitively", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for KConfigLoadError {}
+
+/// Recursively resolves `source`/`rsource`/`osource`/`orsource` directives starting
+/// from a root Kconfig file, producing a flattened, in-order list of blocks.
+///
+/// `root` is the search root used to resolve non-relative source types (`source`,
+/// `osource`) - typically `$srctree`. `vars` supplies `$(VAR)` substitutions applied
+/// to each `filename_glob` before it is expanded. Relative source types (`rsource`,
+/// `orsource`) are instead resolved against the directory containing the file that
+/// names them.
+#[derive(Debug)]
+pub struct KConfigLoader {
+    root: PathBuf,
+    vars: HashMap<String, String>,
+    stack: HashSet<PathBuf>,
+}
+
+impl KConfigLoader {
+    pub fn new(root: impl Into<PathBuf>, vars: HashMap<String, String>) -> Self {
+        Self {
+            root: root.into(),
+            vars,
+            stack: HashSet::new(),
+        }
+    }
+
+    /// Loads `path` and recursively resolves every `source`-family directive it
+    /// (transitively) contains, returning a flattened, in-order list of blocks.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<Vec<LoadedBlock<'static>>, KConfigLoadError> {
+        let mut blocks = Vec::new();
+        self.load_into(path.as_ref(), &mut blocks)?;
+        Ok(blocks)
+    }
+
+    fn load_into(&mut self, path: &Path, blocks: &mut Vec<LoadedBlock<'static>>) -> Result<(), KConfigLoadError> {
+        let canonical = path.canonicalize().map_err(|source| KConfigLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        if !self.stack.insert(canonical.clone()) {
+            return Err(KConfigLoadError::Cycle {
+                path: canonical,
+            });
+        }
+
+        let result = self.parse_and_expand(path, &canonical, blocks);
+        self.stack.remove(&canonical);
+        result
+    }
+
+    fn parse_kconfig_file(path: &Path) -> Result<KConfigFile<'static>, KConfigLoadError> {
+        let contents = read_to_string(path).map_err(|source| KConfigLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        // Leak the file's contents so the blocks we return can borrow from them for
+        // the (short) lifetime of the build script that's driving this loader.
+        let contents: &'static str = Box::leak(contents.into_boxed_str());
+
+        let pairs = KConfigFile::parse(Rule::file, contents).map_err(|source| KConfigLoadError::Parse {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })?;
+        KConfigFile::try_from(pairs).map_err(|source| KConfigLoadError::Parse {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })
+    }
+
+    fn parse_and_expand(&mut self, path: &Path, canonical: &Path, blocks: &mut Vec<LoadedBlock<'static>>) -> Result<(), KConfigLoadError> {
+        let file = Self::parse_kconfig_file(path)?;
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+
+        for block in file.blocks {
+            match block {
+                TopLevel::SourceDirective(directive) => self.expand_source(&directive, parent, path, blocks)?,
+                TopLevel::Menu(mut menu) => {
+                    menu.items = self.resolve_items(menu.items, parent, path)?;
+                    blocks.push(LoadedBlock {
+                        path: canonical.to_path_buf(),
+                        block: TopLevel::Menu(menu),
+                    });
+                }
+                TopLevel::Choice(mut choice) => {
+                    choice.items = self.resolve_items(choice.items, parent, path)?;
+                    blocks.push(LoadedBlock {
+                        path: canonical.to_path_buf(),
+                        block: TopLevel::Choice(choice),
+                    });
+                }
+                TopLevel::If(mut if_entry) => {
+                    if_entry.items = self.resolve_items(if_entry.items, parent, path)?;
+                    blocks.push(LoadedBlock {
+                        path: canonical.to_path_buf(),
+                        block: TopLevel::If(if_entry),
+                    });
+                }
+                other => blocks.push(LoadedBlock {
+                    path: canonical.to_path_buf(),
+                    block: other,
+                }),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves every `source`-family directive nested inside a `menu`/`choice`/`if`
+    /// body, recursing into further nesting, and splices each one's resolved blocks
+    /// in place of the directive that named them.
+    fn resolve_items(&mut self, items: Vec<TopLevel<'static>>, parent: &Path, from: &Path) -> Result<Vec<TopLevel<'static>>, KConfigLoadError> {
+        let mut resolved = Vec::with_capacity(items.len());
+
+        for item in items {
+            match item {
+                TopLevel::SourceDirective(directive) => resolved.extend(self.expand_nested_source(&directive, parent, from)?),
+                TopLevel::Menu(mut menu) => {
+                    menu.items = self.resolve_items(menu.items, parent, from)?;
+                    resolved.push(TopLevel::Menu(menu));
+                }
+                TopLevel::Choice(mut choice) => {
+                    choice.items = self.resolve_items(choice.items, parent, from)?;
+                    resolved.push(TopLevel::Choice(choice));
+                }
+                TopLevel::If(mut if_entry) => {
+                    if_entry.items = self.resolve_items(if_entry.items, parent, from)?;
+                    resolved.push(TopLevel::If(if_entry));
+                }
+                other => resolved.push(other),
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    /// Resolves the glob pattern named by a `source`-family directive against its
+    /// base directory, returning the matched files or a `NoMatch`/optional-skip
+    /// result per `directive.source_type`.
+    fn resolve_source_files(&self, directive: &SourceDirective<'_>, parent: &Path, from: &Path) -> Result<Vec<PathBuf>, KConfigLoadError> {
+        let base_dir = if directive.source_type.is_relative() {
+            parent
+        } else {
+            self.root.as_path()
+        };
+
+        let expanded = self.expand_vars(&directive.filename_glob);
+        let pattern = base_dir.join(&expanded).to_string_lossy().into_owned();
+
+        let matches = glob(&pattern).map_err(|source| KConfigLoadError::Pattern {
+            path: from.to_path_buf(),
+            glob: pattern.clone(),
+            source,
+        })?;
+
+        let mut matched_paths: Vec<PathBuf> = matches.filter_map(Result::ok).filter(|p| p.is_file()).collect();
+        matched_paths.sort();
+
+        if matched_paths.is_empty() && !directive.source_type.is_optional() {
+            return Err(KConfigLoadError::NoMatch {
+                path: from.to_path_buf(),
+                glob: pattern,
+            });
+        }
+
+        Ok(matched_paths)
+    }
+
+    fn expand_source(
+        &mut self,
+        directive: &SourceDirective<'_>,
+        parent: &Path,
+        from: &Path,
+        blocks: &mut Vec<LoadedBlock<'static>>,
+    ) -> Result<(), KConfigLoadError> {
+        for matched in self.resolve_source_files(directive, parent, from)? {
+            self.load_into(&matched, blocks)?;
+        }
+
+        Ok(())
+    }
+
+    /// Like `expand_source`, but for a directive nested inside a `menu`/`choice`/`if`
+    /// body: returns the matched file(s)' resolved blocks directly, to be spliced
+    /// into the enclosing body's item list in place of the directive.
+    fn expand_nested_source(&mut self, directive: &SourceDirective<'_>, parent: &Path, from: &Path) -> Result<Vec<TopLevel<'static>>, KConfigLoadError> {
+        let mut items = Vec::new();
+
+        for matched in self.resolve_source_files(directive, parent, from)? {
+            items.extend(self.load_items(&matched)?);
+        }
+
+        Ok(items)
+    }
+
+    fn load_items(&mut self, path: &Path) -> Result<Vec<TopLevel<'static>>, KConfigLoadError> {
+        let canonical = path.canonicalize().map_err(|source| KConfigLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        if !self.stack.insert(canonical.clone()) {
+            return Err(KConfigLoadError::Cycle {
+                path: canonical,
+            });
+        }
+
+        let result = (|| {
+            let file = Self::parse_kconfig_file(path)?;
+            let parent = path.parent().unwrap_or_else(|| Path::new(""));
+            self.resolve_items(file.blocks, parent, path)
+        })();
+        self.stack.remove(&canonical);
+        result
+    }
+
+    /// Expands `$(VAR)` references against `self.vars`, leaving unknown variables
+    /// as an empty string (matching Kconfig's own behavior for unset environment
+    /// variables).
+    fn expand_vars(&self, glob_pattern: &str) -> String {
+        let mut result = String::with_capacity(glob_pattern.len());
+        let mut rest = glob_pattern;
+
+        while let Some(start) = rest.find("$(") {
+            result.push_str(&rest[..start]);
+            rest = &rest[start + 2..];
+
+            match rest.find(')') {
+                Some(end) => {
+                    if let Some(value) = self.vars.get(&rest[..end]) {
+                        result.push_str(value);
+                    }
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    result.push_str("$(");
+                    result.push_str(rest);
+                    rest = "";
+                }
+            }
+        }
+
+        result.push_str(rest);
+        result
+    }
+}
+
+/// The resolved state of a `tristate` symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tristate {
+    No,
+    Module,
+    Yes,
+}
+
+/// The resolved value of a config symbol, typed according to its `bool`/
+/// `tristate`/`int`/`hex`/`string` declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Tristate(Tristate),
+    /// An `int` or `hex` value, kept in its original (decimal or `0x...`) form.
+    Number(String),
+    Str(String),
+}
+
+impl ConfigValue {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Bool(value) => *value,
+            Self::Tristate(t) => !matches!(t, Tristate::No),
+            Self::Number(text) => text.parse::<i64>().map(|n| n != 0).unwrap_or(!text.is_empty()),
+            Self::Str(text) => !text.is_empty(),
+        }
+    }
+
+    pub fn as_text(&self) -> String {
+        match self {
+            Self::Bool(true) => "y".to_string(),
+            Self::Bool(false) => "n".to_string(),
+            Self::Tristate(Tristate::No) => "n".to_string(),
+            Self::Tristate(Tristate::Module) => "m".to_string(),
+            Self::Tristate(Tristate::Yes) => "y".to_string(),
+            Self::Number(text) | Self::Str(text) => text.clone(),
+        }
+    }
+
+    fn from_raw(symbol_type: SymbolType, raw: &str) -> Self {
+        let raw = raw.trim().trim_matches('"');
+        match symbol_type {
+            SymbolType::Bool => Self::Bool(raw == "y"),
+            SymbolType::Tristate => Self::Tristate(match raw {
+                "y" => Tristate::Yes,
+                "m" => Tristate::Module,
+                _ => Tristate::No,
+            }),
+            SymbolType::Int | SymbolType::Hex => Self::Number(raw.to_string()),
+            SymbolType::String => Self::Str(raw.to_string()),
+        }
+    }
+
+    fn off(symbol_type: SymbolType) -> Self {
+        match symbol_type {
+            SymbolType::Bool => Self::Bool(false),
+            SymbolType::Tristate => Self::Tristate(Tristate::No),
+            SymbolType::Int | SymbolType::Hex => Self::Number("0".to_string()),
+            SymbolType::String => Self::Str(String::new()),
+        }
+    }
+}
+
+/// Evaluates a parsed Kconfig tree against a set of externally-supplied symbol
+/// values (e.g. from a `.config`/defconfig), resolving `default`s, `depends on`,
+/// and `select`/`imply` reverse dependencies.
+pub struct ConfigEvaluator<'a> {
+    configs: HashMap<&'a str, (&'a ConfigEntry<'a>, Option<Expr<'a>>)>,
+    values: HashMap<String, String>,
+    resolved: HashMap<String, ConfigValue>,
+    in_progress: HashSet<String>,
+}
+
+impl<'a> ConfigEvaluator<'a> {
+    pub fn new(blocks: &'a [TopLevel<'a>], values: HashMap<String, String>) -> Self {
+        let mut configs = HashMap::new();
+        Self::collect(blocks, None, &mut configs);
+
+        Self {
+            configs,
+            values,
+            resolved: HashMap::new(),
+            in_progress: HashSet::new(),
+        }
+    }
+
+    fn collect(items: &'a [TopLevel<'a>], cond: Option<Expr<'a>>, out: &mut HashMap<&'a str, (&'a ConfigEntry<'a>, Option<Expr<'a>>)>) {
+        for item in items {
+            match item {
+                TopLevel::Config(entry) => {
+                    out.insert(entry.name.as_ref(), (entry, cond.clone()));
+                }
+                TopLevel::Menu(menu) => Self::collect(&menu.items, cond.clone(), out),
+                TopLevel::Choice(choice) => Self::collect(&choice.items, cond.clone(), out),
+                TopLevel::If(if_entry) => {
+                    let nested = Some(match &cond {
+                        Some(outer) => Expr::And(Box::new(outer.clone()), Box::new(if_entry.condition.clone())),
+                        None => if_entry.condition.clone(),
+                    });
+                    Self::collect(&if_entry.items, nested, out);
+                }
+                TopLevel::SourceDirective(_) | TopLevel::Comment(_) | TopLevel::MainMenu(_) => {}
+            }
+        }
+    }
+
+    /// All symbol names known to this evaluator, in no particular order.
+    pub fn symbols(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.configs.keys().copied()
+    }
+
+    /// Whether `name`'s `depends on` expression (and any enclosing `if` conditions)
+    /// currently evaluate true. Symbols that aren't visible shouldn't be emitted.
+    pub fn is_visible(&mut self, name: &str) -> bool {
+        let Some((entry, enclosing_cond)) = self.configs.get(name).map(|(entry, cond)| (*entry, cond.clone())) else {
+            return false;
+        };
+
+        let mut cond = enclosing_cond;
+        for attr in &entry.attrs {
+            if let ConfigAttr::DependsOn { condition } = attr {
+                cond = Some(match cond {
+                    Some(existing) => Expr::And(Box::new(existing), Box::new(condition.clone())),
+                    None => condition.clone(),
+                });
+            }
+        }
+
+        match cond {
+            Some(cond) => self.eval_bool(&cond),
+            None => true,
+        }
+    }
+
+    /// Resolves `name` to its final value: an explicit value from `values` if
+    /// present and visible, else the first `default` whose condition holds, else
+    /// whatever `select`/`imply` force it to, else the type's "off" value.
+    pub fn resolve(&mut self, name: &str) -> ConfigValue {
+        if let Some(value) = self.resolved.get(name) {
+            return value.clone();
+        }
+
+        if !self.in_progress.insert(name.to_string()) {
+            // A symbol referenced while still being resolved (e.g. via a `default`
+            // cycle) resolves to its "off" value rather than recursing forever.
+            return self
+                .configs
+                .get(name)
+                .map(|(entry, _)| ConfigValue::off(Self::symbol_type_of(entry)))
+                .unwrap_or(ConfigValue::Bool(false));
+        }
+
+        let value = self.resolve_uncached(name);
+        self.in_progress.remove(name);
+        self.resolved.insert(name.to_string(), value.clone());
+        value
+    }
+
+    fn resolve_uncached(&mut self, name: &str) -> ConfigValue {
+        let Some((entry, _)) = self.configs.get(name).map(|(entry, cond)| (*entry, cond.clone())) else {
+            return ConfigValue::Bool(false);
+        };
+
+        let symbol_type = Self::symbol_type_of(entry);
+
+        if !self.is_visible(name) {
+            return ConfigValue::off(symbol_type);
+        }
+
+        if let Some(raw) = self.values.get(name).cloned() {
+            return ConfigValue::from_raw(symbol_type, &raw);
+        }
+
+        for attr in &entry.attrs {
+            let ConfigAttr::Default {
+                value,
+                condition,
+            } = attr
+            else {
+                continue;
+            };
+
+            if condition.as_ref().map(|cond| self.eval_bool(cond)).unwrap_or(true) {
+                let text = self.atom_text(value);
+                return ConfigValue::from_raw(symbol_type, &text);
+            }
+        }
+
+
+        if self.is_selected(name) || self.is_implied(name) {
+            return match symbol_type {
+                SymbolType::Tristate => ConfigValue::Tristate(Tristate::Yes),
+                _ => ConfigValue::Bool(true),
+            };
+        }
+
+        ConfigValue::off(symbol_type)
+    }
+
+    fn symbol_type_of(entry: &ConfigEntry<'a>) -> SymbolType {
+        entry
+            .attrs
+            .iter()
+            .find_map(|attr| match attr {
+                ConfigAttr::Type {
+                    symbol_type, ..
+                } => Some(*symbol_type),
+                _ => None,
+            })
+            .unwrap_or(SymbolType::Bool)
+    }
+
+    fn is_selected(&mut self, name: &str) -> bool {
+        self.any_reverse_dependency(name, |attr| match attr {
+            ConfigAttr::Select {
+                symbol,
+                condition,
+            } => Some((symbol, condition)),
+            _ => None,
+        })
+    }
+
+    fn is_implied(&mut self, name: &str) -> bool {
+        self.any_reverse_dependency(name, |attr| match attr {
+            ConfigAttr::Imply {
+                symbol,
+                condition,
+            } => Some((symbol, condition)),
+            _ => None,
+        })
+    }
+
+    fn any_reverse_dependency<'b>(&mut self, name: &str, matcher: impl Fn(&'b ConfigAttr<'a>) -> Option<(&'b Cow<'a, str>, &'b Option<Expr<'a>>)>) -> bool
+    where
+        'a: 'b,
+    {
+        let owners: Vec<&'a str> = self
+            .configs
+            .iter()
+            .filter(|(_, (entry, _))| entry.attrs.iter().any(|attr| matches!(matcher(attr), Some((symbol, _)) if symbol.as_ref() == name)))
+            .map(|(owner, _)| *owner)
+            .collect();
+
+        for owner in owners {
+            if !self.resolve(owner).is_truthy() {
+                continue;
+            }
+
+            let Some((entry, _)) = self.configs.get(owner).map(|(entry, cond)| (*entry, cond.clone())) else {
+                continue;
+            };
+
+            for attr in &entry.attrs {
+                let Some((symbol, condition)) = matcher(attr) else {
+                    continue;
+                };
+                if symbol.as_ref() != name {
+                    continue;
+                }
+                if condition.as_ref().map(|cond| self.eval_bool(cond)).unwrap_or(true) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn atom_text(&mut self, expr: &Expr<'a>) -> String {
+        match expr {
+            // `y`/`m`/`n` are the tristate constants, not symbol references, even
+            // though the grammar can't tell them apart from a bare symbol name.
+            Expr::Symbol(name) if matches!(name.as_ref(), "y" | "m" | "n") => name.to_string(),
+            Expr::Symbol(name) => self.resolve(name).as_text(),
+            Expr::Literal(text) | Expr::Number(text) => text.to_string(),
+            other => {
+                if self.eval_bool(other) {
+                    "y".to_string()
+                } else {
+                    "n".to_string()
+                }
+            }
+        }
+    }
+
+    fn eval_bool(&mut self, expr: &Expr<'a>) -> bool {
+        match expr {
+            Expr::Symbol(name) if matches!(name.as_ref(), "y" | "m") => true,
+            Expr::Symbol(name) if name.as_ref() == "n" => false,
+            Expr::Symbol(name) => self.resolve(name).is_truthy(),
+            Expr::Literal(text) => !text.is_empty(),
+            Expr::Number(text) => text.parse::<i64>().map(|n| n != 0).unwrap_or(!text.is_empty()),
+            Expr::Not(inner) => !self.eval_bool(inner),
+            Expr::And(lhs, rhs) => self.eval_bool(lhs) && self.eval_bool(rhs),
+            Expr::Or(lhs, rhs) => self.eval_bool(lhs) || self.eval_bool(rhs),
+            Expr::Compare(lhs, op, rhs) => self.eval_compare(lhs, *op, rhs),
+        }
+    }
+
+    fn eval_compare(&mut self, lhs: &Expr<'a>, op: CompareOp, rhs: &Expr<'a>) -> bool {
+        let lhs = self.atom_text(lhs);
+        let rhs = self.atom_text(rhs);
+
+        match op {
+            CompareOp::Eq => lhs == rhs,
+            CompareOp::Ne => lhs != rhs,
+            _ => match (Self::parse_int(&lhs), Self::parse_int(&rhs)) {
+                (Some(lhs), Some(rhs)) => Self::compare_ordered(lhs, rhs, op),
+                _ => Self::compare_ordered(lhs, rhs, op),
+            },
+        }
+    }
+
+    /// Parses a Kconfig integer, which may be a plain decimal literal or a
+    /// `0x`/`0X`-prefixed hex literal (as used by `hex`-typed symbols and their
+    /// `range`/`depends on` expressions).
+    fn parse_int(text: &str) -> Option<i64> {
+        match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            Some(hex) => i64::from_str_radix(hex, 16).ok(),
+            None => text.parse::<i64>().ok(),
+        }
+    }
+
+    fn compare_ordered<T: PartialOrd>(lhs: T, rhs: T, op: CompareOp) -> bool {
+        match op {
+            CompareOp::Lt => lhs < rhs,
+            CompareOp::Le => lhs <= rhs,
+            CompareOp::Gt => lhs > rhs,
+            CompareOp::Ge => lhs >= rhs,
+            CompareOp::Eq | CompareOp::Ne => unreachable!("handled in eval_compare"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod config {
     use {super::*, pest::Parser};
@@ -262,4 +1504,237 @@ mod config {
             assert!(matches!(block, TopLevel::SourceDirective(_)));
         }
     }
+
+    #[test]
+    fn test_config_grammar() {
+        let src = "\
+mainmenu \"Test Menu\"
+
+config FOO
+    bool \"Enable foo\"
+    default y
+    help
+      This is help text
+      for FOO.
+
+menuconfig BAR
+    tristate \"Enable bar\"
+    depends on FOO && !BAZ
+    select BAZ if FOO
+
+choice
+    prompt \"Pick one\"
+    default CHOICE_A
+
+config CHOICE_A
+    bool \"A\"
+
+endchoice
+
+menu \"Advanced\"
+
+if FOO || BAR
+comment \"shown when FOO or BAR\"
+endif
+
+endmenu
+";
+
+        let result = KConfigFile::parse(Rule::file, src).unwrap();
+        let file = KConfigFile::try_from(result).unwrap();
+        assert_eq!(file.blocks.len(), 5);
+
+        assert!(matches!(file.blocks[0], TopLevel::MainMenu(_)));
+
+        let TopLevel::Config(foo) = &file.blocks[1] else {
+            panic!("expected a config entry");
+        };
+        assert_eq!(foo.name, "FOO");
+        assert!(!foo.is_menu);
+        assert_eq!(foo.attrs.len(), 3);
+        assert!(matches!(foo.attrs[0], ConfigAttr::Type { symbol_type: SymbolType::Bool, .. }));
+        assert!(matches!(foo.attrs[2], ConfigAttr::Help { .. }));
+
+        let TopLevel::Config(bar) = &file.blocks[2] else {
+            panic!("expected a config entry");
+        };
+        assert!(bar.is_menu);
+        assert!(matches!(bar.attrs[1], ConfigAttr::DependsOn { condition: Expr::And(..) }));
+
+        let TopLevel::Choice(choice) = &file.blocks[3] else {
+            panic!("expected a choice entry");
+        };
+        assert_eq!(choice.items.len(), 1);
+
+        let TopLevel::Menu(menu) = &file.blocks[4] else {
+            panic!("expected a menu entry");
+        };
+        assert_eq!(menu.prompt, "Advanced");
+        assert!(matches!(&menu.items[0], TopLevel::If(if_entry) if matches!(if_entry.condition, Expr::Or(..))));
+    }
+
+    #[test]
+    fn test_evaluator() {
+        let src = "\
+config FOO
+    bool \"Enable foo\"
+    default y
+
+config BAR
+    tristate \"Enable bar\"
+    depends on FOO && !BAZ
+    select BAZ if FOO
+
+config QUUX
+    bool \"Enable quux\"
+    depends on BAR
+";
+
+        let result = KConfigFile::parse(Rule::file, src).unwrap();
+        let file = KConfigFile::try_from(result).unwrap();
+
+        let mut evaluator = ConfigEvaluator::new(&file.blocks, HashMap::new());
+        assert_eq!(evaluator.resolve("FOO"), ConfigValue::Bool(true));
+        assert!(evaluator.is_visible("BAR"));
+        // BAR has no default and nothing selects/implies BAR itself, so it's off
+        // even though it's visible.
+        assert_eq!(evaluator.resolve("BAR"), ConfigValue::Tristate(Tristate::No));
+        assert!(!evaluator.is_visible("QUUX"));
+
+        let mut values = HashMap::new();
+        values.insert("BAR".to_string(), "y".to_string());
+        let mut evaluator = ConfigEvaluator::new(&file.blocks, values);
+        assert_eq!(evaluator.resolve("BAR"), ConfigValue::Tristate(Tristate::Yes));
+        assert!(evaluator.is_visible("QUUX"));
+    }
+
+    #[test]
+    fn test_hex_comparison() {
+        let src = "\
+config BASE
+    hex \"Base\"
+    default 0x10
+
+config SMALL
+    bool \"Small\"
+    depends on BASE < 0x20
+
+config BIG
+    bool \"Big\"
+    depends on BASE > 0x20
+";
+
+        let result = KConfigFile::parse(Rule::file, src).unwrap();
+        let file = KConfigFile::try_from(result).unwrap();
+
+        let mut evaluator = ConfigEvaluator::new(&file.blocks, HashMap::new());
+        assert!(evaluator.is_visible("SMALL"));
+        assert!(!evaluator.is_visible("BIG"));
+    }
+}
+
+#[cfg(test)]
+mod loader {
+    use {
+        super::*,
+        std::fs::{create_dir, write},
+        tempfile::tempdir,
+    };
+
+    #[test]
+    fn test_cycle_error() {
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.kconfig"), "rsource \"b.kconfig\"\n").unwrap();
+        write(dir.path().join("b.kconfig"), "rsource \"a.kconfig\"\n").unwrap();
+
+        let mut loader = KConfigLoader::new(dir.path(), HashMap::new());
+        let err = loader.load(dir.path().join("a.kconfig")).unwrap_err();
+        assert!(matches!(err, KConfigLoadError::Cycle { .. }));
+    }
+
+    #[test]
+    fn test_diamond_is_not_a_cycle() {
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.kconfig"), "rsource \"b.kconfig\"\nrsource \"c.kconfig\"\n").unwrap();
+        write(dir.path().join("b.kconfig"), "rsource \"common.kconfig\"\n").unwrap();
+        write(dir.path().join("c.kconfig"), "rsource \"common.kconfig\"\n").unwrap();
+        write(dir.path().join("common.kconfig"), "config COMMON\n    bool \"Common\"\n").unwrap();
+
+        let mut loader = KConfigLoader::new(dir.path(), HashMap::new());
+        let blocks = loader.load(dir.path().join("a.kconfig")).unwrap();
+
+        let common_blocks = blocks.iter().filter(|b| matches!(&b.block, TopLevel::Config(c) if c.name == "COMMON")).count();
+        assert_eq!(common_blocks, 2);
+    }
+
+    #[test]
+    fn test_nonoptional_no_match_is_an_error() {
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.kconfig"), "rsource \"missing-*.kconfig\"\n").unwrap();
+
+        let mut loader = KConfigLoader::new(dir.path(), HashMap::new());
+        let err = loader.load(dir.path().join("a.kconfig")).unwrap_err();
+        assert!(matches!(err, KConfigLoadError::NoMatch { .. }));
+    }
+
+    #[test]
+    fn test_optional_no_match_is_silently_skipped() {
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.kconfig"), "orsource \"missing-*.kconfig\"\n").unwrap();
+
+        let mut loader = KConfigLoader::new(dir.path(), HashMap::new());
+        let blocks = loader.load(dir.path().join("a.kconfig")).unwrap();
+        assert!(blocks.is_empty());
+    }
+
+    #[test]
+    fn test_var_substitution() {
+        let dir = tempdir().unwrap();
+        create_dir(dir.path().join("sub")).unwrap();
+        write(dir.path().join("a.kconfig"), "rsource \"$(SUBDIR)/b.kconfig\"\n").unwrap();
+        write(dir.path().join("sub/b.kconfig"), "config FROM_SUB\n    bool \"From sub\"\n").unwrap();
+
+        let mut vars = HashMap::new();
+        vars.insert("SUBDIR".to_string(), "sub".to_string());
+
+        let mut loader = KConfigLoader::new(dir.path(), vars);
+        let blocks = loader.load(dir.path().join("a.kconfig")).unwrap();
+
+        assert_eq!(blocks.len(), 1);
+        assert!(matches!(&blocks[0].block, TopLevel::Config(c) if c.name == "FROM_SUB"));
+    }
+
+    #[test]
+    fn test_nested_source_inside_menu_is_resolved() {
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.kconfig"), "menu \"X\"\nsource \"b.kconfig\"\nendmenu\n").unwrap();
+        write(dir.path().join("b.kconfig"), "config FROM_MENU\n    bool \"From menu\"\n").unwrap();
+
+        let mut loader = KConfigLoader::new(dir.path(), HashMap::new());
+        let blocks = loader.load(dir.path().join("a.kconfig")).unwrap();
+
+        assert_eq!(blocks.len(), 1);
+        let TopLevel::Menu(menu) = &blocks[0].block else {
+            panic!("expected a menu entry");
+        };
+        assert_eq!(menu.items.len(), 1);
+        assert!(matches!(&menu.items[0], TopLevel::Config(c) if c.name == "FROM_MENU"));
+    }
+
+    #[test]
+    fn test_nested_source_inside_if_is_resolved() {
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.kconfig"), "if FOO\nsource \"b.kconfig\"\nendif\n").unwrap();
+        write(dir.path().join("b.kconfig"), "config FROM_IF\n    bool \"From if\"\n").unwrap();
+
+        let mut loader = KConfigLoader::new(dir.path(), HashMap::new());
+        let blocks = loader.load(dir.path().join("a.kconfig")).unwrap();
+
+        assert_eq!(blocks.len(), 1);
+        let TopLevel::If(if_entry) = &blocks[0].block else {
+            panic!("expected an if entry");
+        };
+        assert_eq!(if_entry.items.len(), 1);
+        assert!(matches!(&if_entry.items[0], TopLevel::Config(c) if c.name == "FROM_IF"));
+    }
 }